@@ -0,0 +1,139 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::RwLock;
+use std::thread::sleep;
+use std::time::Duration;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use streamduck_core::versions::SOCKET_API;
+use streamduck_daemon::socket::daemon_data::SocketAPIVersion;
+use streamduck_daemon::socket::{parse_packet_to_data, send_no_data_packet_with_requester, send_packet_with_requester, JsonRpcError, SocketData, SocketPacket};
+use crate::SDClientError;
+
+/// Turns a JSON-RPC error response into the [std::io::Error] that [SDClientError] already
+/// converts from, so a structured daemon failure reaches the caller as a clear error instead of
+/// [parse_packet_to_data] choking on a response with no `result` to read
+fn remote_error(error: &JsonRpcError) -> std::io::Error {
+    std::io::Error::other(format!("daemon returned error {}: {}", error.code, error.message))
+}
+
+/// Reads `response`'s payload as `Res`, first checking whether the daemon reported a structured
+/// [JsonRpcError] instead of a result; see [remote_error]
+pub(crate) fn read_response<Res: SocketData + DeserializeOwned>(response: &SocketPacket) -> Result<Res, SDClientError> {
+    if let Some(error) = &response.error {
+        return Err(remote_error(error).into());
+    }
+
+    Ok(parse_packet_to_data(response)?)
+}
+
+/// Sends `request` down `stream` and reads back a single line as a raw [SocketPacket], shared by
+/// every client that speaks the daemon's newline-delimited packet protocol over its own kind of
+/// stream (Unix socket, TCP socket, ...). Deliberately stops short of decoding the payload: this
+/// is the half of the round trip [with_reconnect] is allowed to retry after reconnecting, so it
+/// may only fail with a transport-level [std::io::Error]. Whether the packet it hands back
+/// carries a result or a [JsonRpcError] is for the caller to decide with [read_response], *after*
+/// `with_reconnect` has returned, so that a daemon-side application error never triggers a
+/// reconnect-and-resend of a request that might not be idempotent.
+pub(crate) fn process_request<S: Read + Write, Req: SocketData + Serialize>(stream: &mut BufReader<S>, request: &Req) -> std::io::Result<SocketPacket> {
+    send_packet_with_requester(stream.get_mut(), "", request)?;
+
+    let mut line = String::new();
+    stream.read_line(&mut line)?;
+
+    serde_json::from_str(&line).map_err(std::io::Error::from)
+}
+
+/// Same as [process_request], but for requests that carry no payload of their own
+pub(crate) fn process_request_without_data<S: Read + Write, Res: SocketData + DeserializeOwned>(stream: &mut BufReader<S>) -> std::io::Result<SocketPacket> {
+    send_no_data_packet_with_requester::<Res>(stream.get_mut(), "")?;
+
+    let mut line = String::new();
+    stream.read_line(&mut line)?;
+
+    serde_json::from_str(&line).map_err(std::io::Error::from)
+}
+
+/// Backoff schedule for reconnecting a client whose connection to the daemon has dropped,
+/// modeled on adb_monitor's retry strategy: a short pause before retrying a connection that was
+/// healthy a moment ago, and a longer pause between attempts to establish a brand new one.
+#[derive(Clone, Copy, Debug)]
+pub struct ReconnectPolicy {
+    /// How long to wait after a connection drops before the first attempt to reconnect
+    pub retry_delay: Duration,
+
+    /// How long to wait between failed attempts to re-establish the connection itself
+    pub reconnect_delay: Duration,
+
+    /// How many times to attempt reconnecting before giving up and surfacing the error
+    pub max_retries: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            retry_delay: Duration::from_millis(200),
+            reconnect_delay: Duration::from_secs(2),
+            max_retries: 5,
+        }
+    }
+}
+
+/// Dials a fresh connection with `connect` and re-runs the version handshake on it, the same
+/// check `UnixClient::new`/`TcpClient::new` perform when first connecting
+pub(crate) fn connect_and_handshake<S: Read + Write>(connect: impl Fn() -> std::io::Result<S>) -> Result<S, SDClientError> {
+    let stream = connect()?;
+    let mut reader = BufReader::new(stream);
+
+    let packet = process_request_without_data::<_, SocketAPIVersion>(&mut reader)?;
+    let version: SocketAPIVersion = read_response(&packet)?;
+
+    if version.version != SOCKET_API.1 {
+        println!("[Warning] Version of client library doesn't match daemon API version. Client: {}, Daemon: {}", SOCKET_API.1, version.version);
+    }
+
+    Ok(reader.into_inner())
+}
+
+/// Runs `call` against `connection`; if it fails with a transport-level [std::io::Error] (the
+/// connection has likely dropped, e.g. the daemon restarted), reconnects via `connect` following
+/// `policy`'s backoff and retries `call` once against the fresh connection before giving up.
+///
+/// `call` is deliberately bounded to [std::io::Error] rather than [SDClientError]: a daemon that
+/// answered with a structured [JsonRpcError] still has a healthy connection, and re-dialing and
+/// resending in that case would just duplicate a request (some, like `push_screen` or
+/// `paste_buttons`, aren't idempotent) for a failure a fresh connection can't fix. Callers decode
+/// the [JsonRpcError] case out of the raw [SocketPacket] `call` returns with [read_response],
+/// after `with_reconnect` has handed it back.
+pub(crate) fn with_reconnect<S, T>(connection: &RwLock<BufReader<S>>, policy: &ReconnectPolicy, connect: impl Fn() -> std::io::Result<S>, call: impl Fn(&mut BufReader<S>) -> std::io::Result<T>) -> Result<T, SDClientError> {
+    {
+        let mut handle = connection.write().unwrap();
+
+        if let Ok(result) = call(&mut handle) {
+            return Ok(result);
+        }
+    }
+
+    sleep(policy.retry_delay);
+
+    let mut attempt = 0;
+
+    let fresh_stream = loop {
+        match connect_and_handshake(&connect) {
+            Ok(stream) => break stream,
+            Err(err) => {
+                attempt += 1;
+
+                if attempt >= policy.max_retries {
+                    return Err(err);
+                }
+
+                sleep(policy.reconnect_delay);
+            }
+        }
+    };
+
+    let mut handle = connection.write().unwrap();
+    *handle = BufReader::new(fresh_stream);
+
+    Ok(call(&mut handle)?)
+}
@@ -1,30 +1,88 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader};
 use std::os::unix::net::UnixStream;
-use std::sync::{Arc, RwLock};
-use serde::{Serialize};
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::{sleep, spawn};
+use serde::{Serialize, Deserialize};
 use serde::de::DeserializeOwned;
 use streamduck_core::core::button::Button;
 use streamduck_core::core::RawButtonPanel;
 use streamduck_core::modules::components::{ComponentDefinition, UIValue};
 use streamduck_core::modules::PluginMetadata;
+use streamduck_core::socket::{next_request_id, JSONRPC_VERSION};
 use streamduck_core::versions::SOCKET_API;
 use streamduck_daemon::socket::daemon_data::{AddDevice, AddDeviceResult, Device, ListDevices, GetDevice, GetDeviceResult, RemoveDevice, RemoveDeviceResult, SocketAPIVersion, ReloadDeviceConfigsResult, ReloadDeviceConfigResult, SaveDeviceConfigsResult, SaveDeviceConfigResult, SetBrightnessResult, ReloadDeviceConfig, SaveDeviceConfig, SetBrightness, ListModules, ListComponents, GetButtonResult, SetButtonResult, ClearButtonResult, PushScreenResult, PopScreenResult, ReplaceScreenResult, ResetStackResult, CommitChangesToConfigResult, GetStackResult, GetCurrentScreenResult, GetStack, GetCurrentScreen, GetButton, SetButton, ClearButton, PushScreen, PopScreen, ReplaceScreen, ResetStack, CommitChangesToConfig, DoButtonActionResult, DoButtonAction, ForciblyPopScreenResult, ForciblyPopScreen, AddComponentResult, GetComponentValuesResult, SetComponentValueResult, RemoveComponentResult, AddComponent, GetComponentValues, SetComponentValue, RemoveComponent};
-use streamduck_daemon::socket::{parse_packet_to_data, send_no_data_packet_with_requester, send_packet_with_requester, SocketData, SocketPacket};
-use crate::{SDClient, SDClientError};
+use streamduck_daemon::socket::{parse_packet_to_data, send_packet_with_requester, SocketData, SocketPacket};
+use crate::{transport, SDClient, SDClientError};
+use crate::transport::ReconnectPolicy;
 use std::io::Write;
 
+/// Table of requests still waiting on a reply, keyed by the [SocketPacket::id] they were sent
+/// with; see [UnixClient::send]
+type PendingTable = Arc<Mutex<HashMap<u64, Sender<SocketPacket>>>>;
+
 /// Definition of Unix Socket based client
+///
+/// Unlike [crate::tcp::TcpClient], this doesn't serialize every call behind a single
+/// write-then-read-a-line: a background thread (spawned by [spawn_reader]) owns the read half of
+/// the connection and demultiplexes replies by [SocketPacket::id] against `pending`, so many
+/// callers can have a request in flight on this one connection at once instead of queuing behind
+/// whichever call went first.
 pub struct UnixClient {
-    connection: RwLock<BufReader<UnixStream>>
+    write_half: Mutex<UnixStream>,
+    pending: PendingTable,
+    reconnect_policy: ReconnectPolicy,
+}
+
+/// Reads newline-delimited [SocketPacket]s off `stream` until it closes, handing each one to the
+/// caller in `pending` still waiting on its [SocketPacket::id]. Packets with no id (notifications)
+/// and replies for an id nothing is waiting on are dropped. Once the connection closes,
+/// every sender still left in `pending` is dropped so a caller blocked in `try_send`'s
+/// `rx.recv()` (e.g. the daemon restarted mid-request) gets woken with an `Err` instead of
+/// hanging forever, letting [UnixClient::send] fall through to [UnixClient::reconnect].
+fn spawn_reader(stream: UnixStream, pending: PendingTable) {
+    spawn(move || {
+        let mut reader = BufReader::new(stream);
+
+        loop {
+            let mut line = String::new();
+
+            if matches!(reader.read_line(&mut line), Ok(0) | Err(_)) {
+                break;
+            }
+
+            let Ok(packet) = serde_json::from_str::<SocketPacket>(&line) else { continue };
+            let Some(id) = packet.id else { continue };
+
+            if let Some(sender) = pending.lock().unwrap().remove(&id) {
+                sender.send(packet).ok();
+            }
+        }
+
+        pending.lock().unwrap().clear();
+    });
 }
 
 #[allow(dead_code)]
 impl UnixClient {
-    /// Initializes client using unix domain socket
+    /// Initializes client using unix domain socket, reconnecting on dropped connections with
+    /// [ReconnectPolicy::default]'s backoff
     pub fn new() -> Result<Arc<Box<dyn SDClient>>, std::io::Error> {
+        Self::new_with_policy(ReconnectPolicy::default())
+    }
+
+    /// Same as [Self::new], but with the reconnect backoff and retry count under the caller's
+    /// control instead of the defaults
+    pub fn new_with_policy(reconnect_policy: ReconnectPolicy) -> Result<Arc<Box<dyn SDClient>>, std::io::Error> {
+        let stream = Self::connect()?;
+        let pending: PendingTable = Arc::new(Mutex::new(HashMap::new()));
+        spawn_reader(stream.try_clone()?, pending.clone());
+
         let client: Arc<Box<dyn SDClient>> = Arc::new(Box::new(UnixClient {
-            connection: RwLock::new(BufReader::new(UnixStream::connect("/tmp/streamduck.sock")?))
+            write_half: Mutex::new(stream),
+            pending,
+            reconnect_policy,
         }));
 
         let daemon_version = client.version().expect("Failed to retrieve version");
@@ -36,31 +94,233 @@ impl UnixClient {
         Ok(client)
     }
 
-    fn process_request<Req: SocketData + Serialize, Res: SocketData + DeserializeOwned>(&self, request: &Req) -> Result<Res, SDClientError> {
-        let mut handle = self.connection.write().unwrap();
+    fn connect() -> std::io::Result<UnixStream> {
+        UnixStream::connect("/tmp/streamduck.sock")
+    }
 
-        send_packet_with_requester(handle.get_mut(), "", request)?;
+    /// Sends `packet` and blocks for the reply [spawn_reader]'s background thread matches back to
+    /// it, stamping a fresh correlation id on first if `packet` doesn't already carry one. Retries
+    /// once after a backed-off [Self::reconnect] if the first attempt hits a connection-level
+    /// failure (the write errored, or the reader thread dropped the waiting sender because the
+    /// socket closed); an ordinary application error still arrives as an ordinary reply and is
+    /// never retried.
+    fn send(&self, mut packet: SocketPacket) -> Result<SocketPacket, SDClientError> {
+        if packet.id.is_none() {
+            packet.id = Some(next_request_id());
+        }
 
-        let mut line = String::new();
-        handle.read_line(&mut line)?;
+        match self.try_send(&packet) {
+            Ok(response) => Ok(response),
+            Err(_) => {
+                self.reconnect()?;
+                self.try_send(&packet)
+            }
+        }
+    }
 
-        let packet: SocketPacket = serde_json::from_str(&line)?;
+    fn try_send(&self, packet: &SocketPacket) -> Result<SocketPacket, SDClientError> {
+        let id = packet.id.expect("UnixClient::send always assigns an id before calling try_send");
+        let (tx, rx) = channel();
 
-        Ok(parse_packet_to_data(&packet)?)
+        self.pending.lock().unwrap().insert(id, tx);
+
+        let write_result = {
+            let mut stream = self.write_half.lock().unwrap();
+            writeln!(stream, "{}", serde_json::to_string(packet)?)
+        };
+
+        if let Err(err) = write_result {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(err.into());
+        }
+
+        rx.recv().map_err(|_| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "connection closed before a reply arrived").into())
     }
 
-    fn process_request_without_data<Res: SocketData + DeserializeOwned>(&self) -> Result<Res, SDClientError> {
-        let mut handle = self.connection.write().unwrap();
+    /// Re-dials the daemon with [ReconnectPolicy]'s backoff, swaps in a fresh [spawn_reader]
+    /// thread for the new connection and re-runs the version handshake on it, the
+    /// connection-level recovery [crate::transport::with_reconnect] performs for the
+    /// single-connection clients
+    fn reconnect(&self) -> Result<(), SDClientError> {
+        sleep(self.reconnect_policy.retry_delay);
+
+        let mut attempt = 0;
 
-        send_no_data_packet_with_requester::<Res>(handle.get_mut(), "")?;
+        let stream = loop {
+            match Self::connect() {
+                Ok(stream) => break stream,
+                Err(err) => {
+                    attempt += 1;
 
-        let mut line = String::new();
-        handle.read_line(&mut line)?;
+                    if attempt >= self.reconnect_policy.max_retries {
+                        return Err(err.into());
+                    }
 
-        let packet: SocketPacket = serde_json::from_str(&line)?;
+                    sleep(self.reconnect_policy.reconnect_delay);
+                }
+            }
+        };
 
-        Ok(parse_packet_to_data(&packet)?)
+        spawn_reader(stream.try_clone()?, self.pending.clone());
+        *self.write_half.lock().unwrap() = stream;
+
+        let handshake = SocketPacket {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: Some(SocketAPIVersion::NAME.to_string()),
+            params: None,
+            result: None,
+            error: None,
+            id: Some(next_request_id()),
+        };
+        let version: SocketAPIVersion = transport::read_response(&self.try_send(&handshake)?)?;
+
+        if version.version != SOCKET_API.1 {
+            println!("[Warning] Version of client library doesn't match daemon API version. Client: {}, Daemon: {}", SOCKET_API.1, version.version);
+        }
+
+        Ok(())
     }
+
+    fn process_request<Req: SocketData + Serialize, Res: SocketData + DeserializeOwned>(&self, request: &Req) -> Result<Res, SDClientError> {
+        let response = self.send(SocketPacket::request(request, next_request_id()))?;
+
+        transport::read_response(&response)
+    }
+
+    fn process_request_without_data<Res: SocketData + DeserializeOwned>(&self) -> Result<Res, SDClientError> {
+        let packet = SocketPacket {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: Some(Res::NAME.to_string()),
+            params: None,
+            result: None,
+            error: None,
+            id: Some(next_request_id()),
+        };
+
+        let response = self.send(packet)?;
+
+        transport::read_response(&response)
+    }
+
+    /// Subscribes to device/button events pushed by the daemon, modeled on adb's
+    /// `host:track-devices`: a one-time subscribe packet is sent over its own connection,
+    /// after which the daemon streams newline-delimited [SocketEvent]s on it indefinitely.
+    /// A background thread owns that connection and forwards every event over the returned
+    /// channel, reconnecting (and resubscribing) if the daemon drops it. Before each
+    /// (re)subscribe, [Self::sync_known_devices] fetches a fresh [ListDevices] snapshot and
+    /// diffs it against a local mirror of known serials, synthesizing the
+    /// [SocketEvent::DeviceAdded]/[SocketEvent::DeviceRemoved] a plain resume would otherwise
+    /// miss for whatever connected or disconnected while this subscription was down.
+    pub fn subscribe_events(&self) -> Result<Receiver<SocketEvent>, SDClientError> {
+        let mut stream = UnixStream::connect("/tmp/streamduck.sock")?;
+        let (tx, rx) = channel();
+
+        let known_devices = Self::sync_known_devices(&mut stream, &HashSet::new(), &tx)?;
+        send_packet_with_requester(&mut stream, "", &SubscribeEvents)?;
+
+        spawn(move || {
+            let mut known_devices = known_devices;
+            let mut reader = BufReader::new(stream);
+
+            loop {
+                let mut line = String::new();
+
+                if matches!(reader.read_line(&mut line), Ok(0) | Err(_)) {
+                    let Ok(mut new_stream) = UnixStream::connect("/tmp/streamduck.sock") else {
+                        break;
+                    };
+
+                    known_devices = match Self::sync_known_devices(&mut new_stream, &known_devices, &tx) {
+                        Ok(devices) => devices,
+                        Err(_) => break,
+                    };
+
+                    if send_packet_with_requester(&mut new_stream, "", &SubscribeEvents).is_err() {
+                        break;
+                    }
+
+                    reader = BufReader::new(new_stream);
+                    continue;
+                }
+
+                let Ok(packet) = serde_json::from_str::<SocketPacket>(&line) else { continue };
+                let Ok(event) = parse_packet_to_data::<SocketEvent>(&packet) else { continue };
+
+                match &event {
+                    SocketEvent::DeviceAdded(device) => { known_devices.insert(device.serial_number.clone()); }
+                    SocketEvent::DeviceRemoved(serial_number) => { known_devices.remove(serial_number); }
+                    _ => {}
+                }
+
+                if tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+
+    /// Fetches the daemon's current device list over `stream`, diffs the serials against
+    /// `known_devices`, and sends synthesized [SocketEvent::DeviceAdded]/[SocketEvent::DeviceRemoved]
+    /// for whatever changed since the last snapshot. Returns the fresh set of known serials,
+    /// which becomes `known_devices` for the next call. See [Self::subscribe_events].
+    fn sync_known_devices(stream: &mut UnixStream, known_devices: &HashSet<String>, tx: &Sender<SocketEvent>) -> Result<HashSet<String>, SDClientError> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let packet = transport::process_request_without_data::<_, ListDevices>(&mut reader)?;
+        let response: ListDevices = transport::read_response(&packet)?;
+
+        let fresh_devices: HashSet<String> = response.devices.iter()
+            .map(|device| device.serial_number.clone())
+            .collect();
+
+        for serial_number in known_devices.difference(&fresh_devices) {
+            tx.send(SocketEvent::DeviceRemoved(serial_number.clone())).ok();
+        }
+
+        for device in response.devices {
+            if !known_devices.contains(&device.serial_number) {
+                tx.send(SocketEvent::DeviceAdded(device)).ok();
+            }
+        }
+
+        Ok(fresh_devices)
+    }
+}
+
+/// A one-time request that turns the sending connection into an event stream, see
+/// [UnixClient::subscribe_events]
+#[derive(Serialize)]
+struct SubscribeEvents;
+
+impl SocketData for SubscribeEvents {
+    const NAME: &'static str = "subscribe_events";
+}
+
+/// A single push event delivered to a [UnixClient::subscribe_events] subscriber
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SocketEvent {
+    /// A device was connected to the daemon
+    DeviceAdded(Device),
+
+    /// A device with this serial number was disconnected from the daemon
+    DeviceRemoved(String),
+
+    /// A device's brightness was changed
+    BrightnessChanged { serial_number: String, brightness: u8 },
+
+    /// A button on the device's current screen was pressed
+    ButtonPressed { serial_number: String, key: u8 },
+
+    /// A new screen was pushed onto a device's stack
+    ScreenPushed { serial_number: String },
+
+    /// The top screen was popped off a device's stack
+    ScreenPopped { serial_number: String },
+}
+
+impl SocketData for SocketEvent {
+    const NAME: &'static str = "subscribe_events";
 }
 
 impl SDClient for UnixClient {
@@ -295,18 +555,12 @@ impl SDClient for UnixClient {
     }
 
     fn send_packet(&self, packet: SocketPacket) -> Result<SocketPacket, SDClientError> {
-        let mut handle = self.connection.write().unwrap();
-        writeln!(handle.get_mut(), "{}", serde_json::to_string(&packet)?)?;
-
-        let mut line = String::new();
-        handle.read_line(&mut line)?;
-
-        Ok(serde_json::from_str(&line)?)
+        self.send(packet)
     }
 
     fn send_packet_without_response(&self, packet: SocketPacket) -> Result<(), SDClientError> {
-        let mut handle = self.connection.write().unwrap();
-        writeln!(handle.get_mut(), "{}", serde_json::to_string(&packet)?)?;
+        let mut stream = self.write_half.lock().unwrap();
+        writeln!(stream, "{}", serde_json::to_string(&packet)?)?;
         Ok(())
     }
 }
\ No newline at end of file
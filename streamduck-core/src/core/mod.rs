@@ -4,6 +4,12 @@ pub mod button;
 /// Methods for interacting with the core
 pub mod methods;
 
+/// Sandboxed scripting support for dynamic button text and backgrounds
+pub mod scripting;
+
+/// Live screen/region capture as a button background via PipeWire/xdg-desktop-portal
+pub mod screencast;
+
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex, RwLock};
 use std::sync::mpsc::{channel, Receiver};
@@ -0,0 +1,224 @@
+//! Live screen/region capture as a button background
+//!
+//! Mirrors how a compositor like niri exposes its own output through the
+//! xdg-desktop-portal `ScreenCast` interface: a portal session hands back a PipeWire node
+//! id, a stream is connected to that node, and every delivered buffer is converted into an
+//! [image::RgbaImage] that the device thread's [crate::core::thread::process_animations]
+//! picks up on its next poll, the same way it already drains [crate::core::scripting]
+//! output and animated `ExistingImage` frames.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::spawn;
+use ashpd::desktop::screencast::{CursorMode, Screencast, SourceType};
+use ashpd::enumflags2::BitFlags;
+use image::RgbaImage;
+use image::imageops::FilterType;
+use pipewire::spa::param::format::{MediaSubtype, MediaType};
+use pipewire::spa::param::format_utils;
+use pipewire::spa::param::video::{VideoFormat, VideoInfoRaw};
+use pipewire::spa::pod::Pod;
+use pipewire::stream::{Stream, StreamFlags};
+
+/// Width/height/pixel format negotiated on the PipeWire stream's `SPA_PARAM_Format`, needed to
+/// make sense of the flat byte buffer each frame arrives as. The connection is left to
+/// `StreamFlags::AUTOCONNECT` with no format params of its own, so the portal/PipeWire server
+/// picks whichever raw video format it prefers (commonly BGRx/BGRA rather than RGBA) — decoding
+/// always has to check which one actually got negotiated instead of assuming RGBA.
+#[derive(Clone, Copy)]
+struct CaptureFormat {
+    width: u32,
+    height: u32,
+    pixel_format: VideoFormat,
+}
+
+/// Latest frame captured from a portal `ScreenCast` session, shared with the device thread
+pub struct ScreencastHandle {
+    frame: RwLock<Option<RgbaImage>>,
+    new_frame: AtomicBool,
+    format: RwLock<Option<CaptureFormat>>,
+}
+
+impl ScreencastHandle {
+    fn new() -> Arc<ScreencastHandle> {
+        Arc::new(ScreencastHandle {
+            frame: RwLock::new(None),
+            new_frame: AtomicBool::new(false),
+            format: RwLock::new(None),
+        })
+    }
+
+    /// Takes the latest frame if one has arrived since the last call, scaled to `image_size`
+    pub fn take_new_frame(&self, image_size: (usize, usize)) -> Option<RgbaImage> {
+        if !self.new_frame.swap(false, Ordering::AcqRel) {
+            return None;
+        }
+
+        self.frame.read().unwrap().as_ref().map(|frame| {
+            image::imageops::resize(frame, image_size.0 as u32, image_size.1 as u32, FilterType::Triangle)
+        })
+    }
+
+    fn store_frame(&self, frame: RgbaImage) {
+        *self.frame.write().unwrap() = Some(frame);
+        self.new_frame.store(true, Ordering::Release);
+    }
+
+    fn store_format(&self, width: u32, height: u32, pixel_format: VideoFormat) {
+        *self.format.write().unwrap() = Some(CaptureFormat { width, height, pixel_format });
+    }
+
+    fn format(&self) -> Option<CaptureFormat> {
+        *self.format.read().unwrap()
+    }
+}
+
+/// Opens a portal `ScreenCast` session offering `sources` as the pickable kinds (monitor,
+/// window, or both), connects a PipeWire stream to the resulting node, and spawns a
+/// background thread that feeds every delivered buffer into the returned handle. Capture
+/// frames arrive asynchronously to the device thread's poll loop, which is why the handle
+/// only exposes a lock-free "is there a new frame" check.
+///
+/// Which monitor or window actually gets captured is chosen interactively by the user in the
+/// portal's own picker dialog when the session starts — `sources` only controls which kinds
+/// of source that dialog offers, it can't pre-select a specific one.
+pub fn start_screencast(sources: impl Into<BitFlags<SourceType>>) -> Arc<ScreencastHandle> {
+    let sources = sources.into();
+    let handle = ScreencastHandle::new();
+    let thread_handle = handle.clone();
+
+    spawn(move || {
+        if let Err(err) = run_capture(sources, thread_handle) {
+            log::error!("Screencast capture failed: {}", err);
+        }
+    });
+
+    handle
+}
+
+fn run_capture(sources: BitFlags<SourceType>, handle: Arc<ScreencastHandle>) -> Result<(), Box<dyn std::error::Error>> {
+    let node_id = futures::executor::block_on(negotiate_portal_session(sources))?;
+
+    pipewire::init();
+    let main_loop = pipewire::main_loop::MainLoop::new(None)?;
+    let context = pipewire::context::Context::new(&main_loop)?;
+    let core = context.connect(None)?;
+
+    let stream = Stream::new(&core, "streamduck-screencast", Default::default())?;
+
+    let _listener = stream.add_local_listener_with_user_data(handle)
+        .param_changed(|_stream, handle, id, pod| {
+            let Some(pod) = pod else { return };
+
+            if id != pipewire::spa::param::ParamType::Format.as_raw() {
+                return;
+            }
+
+            let Ok((media_type, media_subtype)) = format_utils::parse_format(pod) else { return };
+
+            if media_type != MediaType::Video || media_subtype != MediaSubtype::Raw {
+                return;
+            }
+
+            let mut info = VideoInfoRaw::new();
+            if info.parse(pod).is_ok() {
+                handle.store_format(info.size().width, info.size().height, info.format());
+            }
+        })
+        .process(|stream, handle| {
+            if let Some(mut buffer) = stream.dequeue_buffer() {
+                let Some(format) = handle.format() else { return };
+
+                if let Some(frame) = decode_buffer(&mut buffer, format) {
+                    handle.store_frame(frame);
+                }
+            }
+        })
+        .register()?;
+
+    stream.connect(
+        pipewire::spa::utils::Direction::Input,
+        Some(node_id),
+        StreamFlags::AUTOCONNECT | StreamFlags::MAP_BUFFERS,
+        &mut [],
+    )?;
+
+    main_loop.run();
+
+    Ok(())
+}
+
+/// Asks the xdg-desktop-portal `ScreenCast` portal for an interactive monitor/window pick
+/// (offering whichever of `sources`'s kinds are set) and returns the PipeWire node id of the
+/// resulting stream
+async fn negotiate_portal_session(sources: BitFlags<SourceType>) -> ashpd::Result<u32> {
+    let proxy = Screencast::new().await?;
+    let session = proxy.create_session().await?;
+
+    proxy.select_sources(
+        &session,
+        CursorMode::Hidden,
+        sources,
+        false,
+        None,
+        Default::default(),
+    ).await?;
+
+    let response = proxy.start(&session, None).await?.response()?;
+
+    response.streams()
+        .first()
+        .map(|stream| stream.pipe_wire_node_id())
+        .ok_or(ashpd::Error::NoResponse)
+}
+
+/// Converts a raw PipeWire buffer (shm or DmaBuf) into an owned [RgbaImage] at the `width` x
+/// `height` negotiated on the stream's `SPA_PARAM_Format`, reordering channels if the server
+/// negotiated a BGR-ordered format rather than RGB-ordered.
+///
+/// The buffer's chunk only tells us how many bytes were written, not how they're laid out, and
+/// PipeWire is free to pad each row out to a stride wider than `width * 4` bytes for alignment.
+/// When that's the case the padding is stripped row-by-row rather than assumed away, otherwise
+/// every row after the first would be read shifted and the image would shear diagonally.
+fn decode_buffer(buffer: &mut pipewire::buffer::Buffer, format: CaptureFormat) -> Option<RgbaImage> {
+    let datas = buffer.datas_mut();
+    let data = datas.first_mut()?;
+    let chunk = data.chunk();
+
+    let stride = chunk.stride() as usize;
+    let row_len = format.width as usize * 4;
+    let bytes = data.data()?;
+
+    if stride == 0 || row_len == 0 || format.height == 0 {
+        return None;
+    }
+
+    if bytes.len() < stride * format.height as usize {
+        return None;
+    }
+
+    let mut packed = Vec::with_capacity(row_len * format.height as usize);
+
+    if stride == row_len {
+        packed.extend_from_slice(&bytes[..row_len * format.height as usize]);
+    } else {
+        for row in 0..format.height as usize {
+            let start = row * stride;
+            packed.extend_from_slice(&bytes[start..start + row_len]);
+        }
+    }
+
+    if matches!(format.pixel_format, VideoFormat::BGRA | VideoFormat::BGRx) {
+        swap_red_and_blue(&mut packed);
+    }
+
+    RgbaImage::from_raw(format.width, format.height, packed)
+}
+
+/// Swaps the red and blue bytes of every pixel in place, turning a BGRA/BGRx buffer into the
+/// RGBA order [RgbaImage] expects, see [decode_buffer]
+fn swap_red_and_blue(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+}
@@ -0,0 +1,243 @@
+//! Minimal sandboxed scripting support for dynamic button content
+//!
+//! Scripts are small Scheme-like s-expressions evaluated by [eval_script] to produce
+//! either the text of a [crate::core::thread::ButtonText] or the color of a
+//! [crate::core::thread::ButtonBackground::Script]. Evaluation must stay side-effect-free
+//! and time-bounded, since it runs on the device thread's poll loop: the host API only
+//! exposes read-only facts (`key`, `time`), and [eval_script] aborts once `FUEL_LIMIT`
+//! expressions have been reduced rather than let a pathological script stall rendering.
+
+use std::fmt;
+
+/// Host state a script can observe, but never mutate
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptContext {
+    /// Index of the key the script is rendering for
+    pub key: u8,
+
+    /// Seconds since the Unix epoch, see [crate::core::thread::current_script_time].
+    /// `f64`, not `f32`: at today's epoch values (~1.75e9) an `f32` can only represent
+    /// multiples of 128s, which would quantize a clock/animation script to multi-minute steps.
+    pub time: f64,
+}
+
+/// Result of evaluating a script
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptValue {
+    Number(f64),
+    Text(String),
+}
+
+impl ScriptValue {
+    /// Coerces the value into text for [crate::core::thread::ButtonText]
+    pub fn into_text(self) -> String {
+        match self {
+            ScriptValue::Number(n) => n.to_string(),
+            ScriptValue::Text(s) => s,
+        }
+    }
+
+    /// Coerces the value into an `(r, g, b, a)` color for [crate::core::thread::ButtonBackground::Script]
+    ///
+    /// Accepts either a space-separated `"r g b"`/`"r g b a"` string, or a single number
+    /// that's repeated across the RGB channels. Anything else falls back to black.
+    pub fn into_color(self) -> (u8, u8, u8, u8) {
+        let text = self.into_text();
+        let channels: Vec<u8> = text.split_whitespace()
+            .filter_map(|part| part.parse::<f64>().ok())
+            .map(|n| n.clamp(0.0, 255.0) as u8)
+            .collect();
+
+        match channels.as_slice() {
+            [r, g, b, a] => (*r, *g, *b, *a),
+            [r, g, b] => (*r, *g, *b, 255),
+            [v] => (*v, *v, *v, 255),
+            _ => (0, 0, 0, 255),
+        }
+    }
+}
+
+/// Error produced while parsing or reducing a script
+#[derive(Debug, Clone)]
+pub enum ScriptError {
+    Parse(String),
+    Eval(String),
+    FuelExhausted,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptError::Parse(msg) => write!(f, "parse error: {}", msg),
+            ScriptError::Eval(msg) => write!(f, "eval error: {}", msg),
+            ScriptError::FuelExhausted => write!(f, "script exceeded its step budget"),
+        }
+    }
+}
+
+/// Number of expression reductions a single [eval_script] call is allowed before aborting,
+/// keeping a misbehaving script from stalling the device thread's poll loop
+const FUEL_LIMIT: u32 = 10_000;
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Number(f64),
+    Symbol(String),
+    Text(String),
+    List(Vec<Expr>),
+}
+
+/// Evaluates `source` against `ctx`, returning the value it reduces to
+///
+/// The evaluator only exposes pure functions (arithmetic, string concatenation, `if`) plus
+/// two host accessors, `(key)` and `(time)`, so a script can never perform IO or block.
+pub fn eval_script(source: &str, ctx: &ScriptContext) -> Result<ScriptValue, ScriptError> {
+    let tokens = tokenize(source);
+    let mut pos = 0;
+    let expr = parse_expr(&tokens, &mut pos)?;
+
+    let mut fuel = FUEL_LIMIT;
+    eval(&expr, ctx, &mut fuel)
+}
+
+fn tokenize(source: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            '(' | ')' => {
+                tokens.push(c.to_string());
+                chars.next();
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        break;
+                    }
+                    text.push(c);
+                }
+                tokens.push(format!("\"{}", text));
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            _ => {
+                let mut token = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+        }
+    }
+
+    tokens
+}
+
+fn parse_expr(tokens: &[String], pos: &mut usize) -> Result<Expr, ScriptError> {
+    let token = tokens.get(*pos).ok_or_else(|| ScriptError::Parse("unexpected end of script".to_string()))?;
+    *pos += 1;
+
+    match token.as_str() {
+        "(" => {
+            let mut list = Vec::new();
+            loop {
+                match tokens.get(*pos).map(String::as_str) {
+                    Some(")") => {
+                        *pos += 1;
+                        break;
+                    }
+                    None => return Err(ScriptError::Parse("missing closing paren".to_string())),
+                    _ => list.push(parse_expr(tokens, pos)?),
+                }
+            }
+            Ok(Expr::List(list))
+        }
+        ")" => Err(ScriptError::Parse("unexpected closing paren".to_string())),
+        _ if token.starts_with('"') => Ok(Expr::Text(token[1..].to_string())),
+        _ => {
+            if let Ok(n) = token.parse::<f64>() {
+                Ok(Expr::Number(n))
+            } else {
+                Ok(Expr::Symbol(token.clone()))
+            }
+        }
+    }
+}
+
+fn eval(expr: &Expr, ctx: &ScriptContext, fuel: &mut u32) -> Result<ScriptValue, ScriptError> {
+    *fuel = fuel.checked_sub(1).ok_or(ScriptError::FuelExhausted)?;
+
+    match expr {
+        Expr::Number(n) => Ok(ScriptValue::Number(*n)),
+        Expr::Text(s) => Ok(ScriptValue::Text(s.clone())),
+        Expr::Symbol(name) => match name.as_str() {
+            "key" => Ok(ScriptValue::Number(ctx.key as f64)),
+            "time" => Ok(ScriptValue::Number(ctx.time)),
+            other => Err(ScriptError::Eval(format!("unknown symbol '{}'", other))),
+        },
+        Expr::List(items) => eval_call(items, ctx, fuel),
+    }
+}
+
+fn eval_call(items: &[Expr], ctx: &ScriptContext, fuel: &mut u32) -> Result<ScriptValue, ScriptError> {
+    let (head, args) = items.split_first().ok_or_else(|| ScriptError::Eval("empty expression".to_string()))?;
+
+    let Expr::Symbol(op) = head else {
+        return Err(ScriptError::Eval("expression head must be a symbol".to_string()));
+    };
+
+    if op == "if" {
+        let [cond, then, otherwise] = args else {
+            return Err(ScriptError::Eval("'if' takes a condition, then-branch and else-branch".to_string()));
+        };
+
+        return if as_number(eval(cond, ctx, fuel)?)? != 0.0 {
+            eval(then, ctx, fuel)
+        } else {
+            eval(otherwise, ctx, fuel)
+        };
+    }
+
+    if op == "key" || op == "time" {
+        return eval(&Expr::Symbol(op.clone()), ctx, fuel);
+    }
+
+    let values: Result<Vec<ScriptValue>, ScriptError> = args.iter()
+        .map(|arg| eval(arg, ctx, fuel))
+        .collect();
+    let values = values?;
+
+    match op.as_str() {
+        "+" => Ok(ScriptValue::Number(numbers(&values)?.iter().sum())),
+        "-" => fold_numeric(&values, |a, b| a - b),
+        "*" => Ok(ScriptValue::Number(numbers(&values)?.iter().product())),
+        "/" => fold_numeric(&values, |a, b| a / b),
+        "concat" => Ok(ScriptValue::Text(values.into_iter().map(ScriptValue::into_text).collect())),
+        other => Err(ScriptError::Eval(format!("unknown function '{}'", other))),
+    }
+}
+
+fn as_number(value: ScriptValue) -> Result<f64, ScriptError> {
+    match value {
+        ScriptValue::Number(n) => Ok(n),
+        ScriptValue::Text(s) => Err(ScriptError::Eval(format!("expected a number, got \"{}\"", s))),
+    }
+}
+
+fn numbers(values: &[ScriptValue]) -> Result<Vec<f64>, ScriptError> {
+    values.iter().cloned().map(as_number).collect()
+}
+
+fn fold_numeric(values: &[ScriptValue], f: impl Fn(f64, f64) -> f64) -> Result<ScriptValue, ScriptError> {
+    let mut numbers = numbers(values)?.into_iter();
+    let first = numbers.next().ok_or_else(|| ScriptError::Eval("expected at least one argument".to_string()))?;
+    Ok(ScriptValue::Number(numbers.fold(first, f)))
+}
@@ -8,22 +8,62 @@ use std::hash::{Hash, Hasher};
 use std::io::{Cursor};
 use std::ops::Deref;
 use serde::{Serialize, Deserialize};
-use std::sync::{Arc, RwLock};
-use std::sync::mpsc::{channel, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
 use std::thread::{sleep, spawn};
 use std::time::{Duration, Instant};
+use font_loader::system_fonts;
 use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
 use image::imageops::{FilterType, tile};
 use image::io::Reader;
-use rusttype::Scale;
+use rusttype::{Font, Scale};
 use streamdeck::{Colour, DeviceImage, ImageMode, StreamDeck};
 use crate::core::{SDCore, UniqueButton};
 use crate::core::button::{Component, parse_unique_button_to_component};
 use crate::core::methods::{CoreHandle, get_current_screen};
+use crate::core::scripting::{eval_script, ScriptContext, ScriptError, ScriptValue};
+use crate::core::screencast::{start_screencast, ScreencastHandle};
+use ashpd::desktop::screencast::SourceType;
 use crate::font::get_font_from_collection;
 use crate::images::{AnimationFrame, convert_image, SDImage};
 use crate::util::rendering::{image_from_horiz_gradient, image_from_solid, image_from_vert_gradient, render_aligned_shadowed_text_on_image, render_aligned_text_on_image, TextAlignment};
 
+/// Fonts that were loaded from the OS on demand, keyed by requested family name,
+/// so a system font lookup only ever hits `font-loader` once per family.
+static SYSTEM_FONT_CACHE: Mutex<Option<HashMap<String, Option<Arc<Font<'static>>>>>> = Mutex::new(None);
+
+/// Resolves a font from the bundled collection, falling back to loading an installed
+/// system font via `font-loader` when `family` isn't in it. Used by [draw_foreground].
+fn resolve_font(family: &str) -> Option<Arc<Font<'static>>> {
+    if let Some(font) = get_font_from_collection(family) {
+        return Some(font);
+    }
+
+    let mut cache = SYSTEM_FONT_CACHE.lock().unwrap();
+    let cache = cache.get_or_insert_with(HashMap::new);
+
+    if let Some(font) = cache.get(family) {
+        return font.clone();
+    }
+
+    let font = load_system_font(family);
+    cache.insert(family.to_string(), font.clone());
+    font
+}
+
+/// Loads a TTF from the OS via `font-loader`, trying `family` first and, if the exact family
+/// isn't installed, whatever font an unconstrained property query returns.
+fn load_system_font(family: &str) -> Option<Arc<Font<'static>>> {
+    let property = system_fonts::FontPropertyBuilder::new().family(family).build();
+
+    let (bytes, _) = system_fonts::get(&property).or_else(|| {
+        let fallback = system_fonts::FontPropertyBuilder::new().build();
+        system_fonts::get(&fallback)
+    })?;
+
+    Font::try_from_vec(bytes).map(Arc::new)
+}
+
 pub type ImageCollection = Arc<RwLock<HashMap<String, SDImage>>>;
 
 /// Handle for contacting renderer thread
@@ -41,6 +81,52 @@ impl DeviceThreadHandle {
     pub fn send(&self, commands: Vec<DeviceThreadCommunication>) {
         self.tx.send(commands).ok();
     }
+
+    /// Subscribes to button-image updates as they're rendered, so a remote UI can mirror the
+    /// deck without polling. A full snapshot of every currently rendered key is sent
+    /// immediately, after which only keys whose rendered hash actually changed are sent,
+    /// reusing the same damage tracking the device thread uses for its own writes.
+    pub fn subscribe(&self) -> Receiver<PreviewUpdate> {
+        let (tx, rx) = channel();
+
+        {
+            let current_images = self.state.current_images.read().unwrap();
+            for (&key, image) in current_images.iter() {
+                tx.send(PreviewUpdate { key, image: encode_preview_image(image, self.state.image_mode) }).ok();
+            }
+        }
+
+        self.state.subscribers.lock().unwrap().push(tx);
+
+        rx
+    }
+}
+
+/// A single button image update pushed to a [DeviceThreadHandle::subscribe] subscriber,
+/// encoded in the connected device's [ImageMode] so a remote client can use it as-is
+pub struct PreviewUpdate {
+    pub key: u8,
+    pub image: Vec<u8>,
+}
+
+fn encode_preview_image(image: &DynamicImage, image_mode: ImageMode) -> Vec<u8> {
+    let mut buffer = vec![];
+
+    image.write_to(&mut Cursor::new(&mut buffer), match image_mode {
+        ImageMode::Bmp => ImageFormat::Bmp,
+        ImageMode::Jpeg => ImageFormat::Jpeg,
+    }).ok();
+
+    buffer
+}
+
+/// Pushes `image` as the latest render of `key` to every live subscriber, dropping any
+/// whose receiver has gone away
+fn publish_preview(state: &RendererState, key: u8, image: &DynamicImage) {
+    let mut subscribers = state.subscribers.lock().unwrap();
+    let encoded = encode_preview_image(image, state.image_mode);
+
+    subscribers.retain(|tx| tx.send(PreviewUpdate { key, image: encoded.clone() }).is_ok());
 }
 
 #[allow(dead_code)]
@@ -63,6 +149,8 @@ pub enum DeviceThreadCommunication {
 
 pub struct RendererState {
     pub current_images: RwLock<HashMap<u8, DynamicImage>>,
+    image_mode: ImageMode,
+    subscribers: Mutex<Vec<Sender<PreviewUpdate>>>,
 }
 
 /// Spawns device thread from a core reference
@@ -71,6 +159,8 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
 
     let state = Arc::new(RendererState {
         current_images: Default::default(),
+        image_mode: streamdeck.kind().image_mode(),
+        subscribers: Mutex::new(Vec::new()),
     });
 
     let renderer_state = state.clone();
@@ -110,7 +200,7 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
 
         let mut missing = DynamicImage::ImageRgba8(frame);
 
-        if let Some(font) = get_font_from_collection("default") {
+        if let Some(font) = resolve_font("default") {
             render_aligned_shadowed_text_on_image(
                 (iw, ih),
                 &mut missing,
@@ -145,6 +235,8 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
         let mut renderer_map = HashMap::new();
         let mut animation_cache: HashMap<u64, Arc<DeviceImage>> = HashMap::new();
         let mut renderer_cache: HashMap<u64, DynamicImage> = HashMap::new();
+        let mut screencasts: HashMap<String, Arc<ScreencastHandle>> = HashMap::new();
+        let mut damage: HashMap<u8, u64> = HashMap::new();
         loop {
             if core.core.is_closed() {
                 break;
@@ -191,7 +283,7 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
                 Ok(com) => {
                     for com in com {
                         match com {
-                            DeviceThreadCommunication::Redraw => redraw(&core, &mut streamdeck, &renderer_state, &missing, &mut renderer_cache, &mut renderer_map, &mut animation_counters),
+                            DeviceThreadCommunication::Redraw => redraw(&core, &mut streamdeck, &renderer_state, &missing, &mut renderer_cache, &mut renderer_map, &mut animation_counters, &mut damage),
 
                             DeviceThreadCommunication::SetBrightness(brightness) => {
                                 streamdeck.set_brightness(brightness).ok();
@@ -230,7 +322,7 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
                 }
             }
 
-            process_animations(&core, &mut streamdeck, &mut animation_cache, &mut animation_counters, &mut renderer_map);
+            process_animations(&core, &mut streamdeck, &renderer_state, &mut animation_cache, &mut animation_counters, &mut renderer_map, &missing, &mut screencasts, &mut damage);
 
             // Rate limiter
             let rate = 1.0 / core.core.pool_rate as f32;
@@ -308,8 +400,58 @@ impl AnimationCounter {
     }
 }
 
-fn process_animations(core: &CoreHandle, streamdeck: &mut StreamDeck, cache: &mut HashMap<u64, Arc<DeviceImage>>, counters: &mut HashMap<String, AnimationCounter>, renderer_map: &mut HashMap<u8, (UniqueButton, RendererComponent)>) {
+fn process_animations(core: &CoreHandle, streamdeck: &mut StreamDeck, state: &RendererState, cache: &mut HashMap<u64, Arc<DeviceImage>>, counters: &mut HashMap<String, AnimationCounter>, renderer_map: &mut HashMap<u8, (UniqueButton, RendererComponent)>, missing: &DynamicImage, screencasts: &mut HashMap<String, Arc<ScreencastHandle>>, damage: &mut HashMap<u8, u64>) {
     for (key, (button, component)) in renderer_map {
+        // Scripted content has no stable hash to cache against, so it's re-evaluated every pass
+        // and redrawn whenever its evaluated output changes, rather than routed through the
+        // to_cache lookup below, see crate::core::scripting
+        if component_has_script(component) {
+            let scripted = evaluate_scripts(component, *key);
+            let hash = hash_scripted_component(core, button, component, &scripted);
+
+            if mark_if_changed(damage, *key, hash) {
+                let background = draw_background(component, core, missing, counters, *key, &scripted);
+                let image = draw_foreground(component, button, background, core, *key, &scripted);
+
+                let mut buffer = vec![];
+                image.rotate180().write_to(&mut Cursor::new(&mut buffer), match core.core.kind.image_mode() {
+                    ImageMode::Bmp => ImageFormat::Bmp,
+                    ImageMode::Jpeg => ImageFormat::Jpeg,
+                }).ok();
+
+                streamdeck.write_button_image(*key, &DeviceImage::from(buffer)).ok();
+                publish_preview(state, *key, &image);
+            }
+
+            continue;
+        }
+
+        // Every screencast frame differs from the last, so caching is forced off here too
+        if let ButtonBackground::Screencast(identifier) = &component.background {
+            // `identifier` only keys which concurrently-running capture session this button
+            // reads from; it carries no source selection of its own. Which monitor or window
+            // actually gets captured is picked interactively in the portal's dialog when the
+            // session starts, so both kinds are offered there rather than hardcoding monitors.
+            let stream = screencasts.entry(identifier.clone())
+                .or_insert_with(|| start_screencast(SourceType::Monitor | SourceType::Window));
+
+            if let Some(frame) = stream.take_new_frame(core.core.image_size) {
+                let background = DynamicImage::ImageRgba8(frame);
+                let image = draw_foreground(component, button, background, core, *key, &ScriptedValues::none(component));
+
+                let mut buffer = vec![];
+                image.rotate180().write_to(&mut Cursor::new(&mut buffer), match core.core.kind.image_mode() {
+                    ImageMode::Bmp => ImageFormat::Bmp,
+                    ImageMode::Jpeg => ImageFormat::Jpeg,
+                }).ok();
+
+                streamdeck.write_button_image(*key, &DeviceImage::from(buffer)).ok();
+                publish_preview(state, *key, &image);
+            }
+
+            continue;
+        }
+
         if let ButtonBackground::ExistingImage(identifier) = &component.background {
             let counter = if let Some(counter) = counters.get_mut(identifier) {
                 Some(counter)
@@ -338,27 +480,32 @@ fn process_animations(core: &CoreHandle, streamdeck: &mut StreamDeck, cache: &mu
 
                     let hash = hasher.finish();
 
-                    let variant = cache.get(&hash);
+                    if mark_if_changed(damage, *key, hash) {
+                        let variant = cache.get(&hash);
 
-                    if component.to_cache && variant.is_some() {
-                        // TODO: Check previous cache and if equal, skip this
-                        streamdeck.write_button_image(*key, variant.unwrap().deref()).ok();
-                    } else {
-                        let mut buffer = vec![];
+                        if component.to_cache && variant.is_some() {
+                            // Cached device-encoded image has no decoded DynamicImage handy,
+                            // so preview subscribers just miss this particular frame; the next
+                            // content change will catch them back up
+                            streamdeck.write_button_image(*key, variant.unwrap().deref()).ok();
+                        } else {
+                            let image = draw_foreground(&component, &button, frame.image.clone(), core, *key, &ScriptedValues::none(component));
 
-                        draw_foreground(&component, &button, frame.image.clone(), core).rotate180().write_to(&mut Cursor::new(&mut buffer), match core.core.kind.image_mode() {
-                            ImageMode::Bmp => ImageFormat::Bmp,
-                            ImageMode::Jpeg => ImageFormat::Jpeg,
-                        }).ok();
+                            let mut buffer = vec![];
+                            image.rotate180().write_to(&mut Cursor::new(&mut buffer), match core.core.kind.image_mode() {
+                                ImageMode::Bmp => ImageFormat::Bmp,
+                                ImageMode::Jpeg => ImageFormat::Jpeg,
+                            }).ok();
 
-                        let arc = Arc::new(DeviceImage::from(buffer));
+                            let arc = Arc::new(DeviceImage::from(buffer));
 
-                        if component.to_cache {
-                            cache.insert(hash, arc.clone());
-                            println!("caching: {}", hash);
-                        }
+                            if component.to_cache {
+                                cache.insert(hash, arc.clone());
+                            }
 
-                        streamdeck.write_button_image(*key, arc.deref()).ok();
+                            streamdeck.write_button_image(*key, arc.deref()).ok();
+                            publish_preview(state, *key, &image);
+                        }
                     }
                 }
             }
@@ -371,7 +518,104 @@ fn process_animations(core: &CoreHandle, streamdeck: &mut StreamDeck, cache: &mu
     };
 }
 
-fn draw_background(renderer: &RendererComponent, core: &CoreHandle, missing: &DynamicImage, counters: &mut HashMap<String, AnimationCounter>) -> DynamicImage {
+/// Seconds since the Unix epoch, exposed to scripts as `(time)`. `f64` so the value stays
+/// precise to sub-millisecond granularity at today's epoch magnitude instead of quantizing
+/// to multi-minute steps the way an `f32` would, see [ScriptContext::time].
+pub(crate) fn current_script_time() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64()
+}
+
+/// A component's scripted background/text outputs, evaluated once per frame so the value a
+/// redraw decision hashes against is exactly the value that gets drawn. Evaluating twice (once
+/// to hash, once to render) would sample `(time)` independently each time, and the two could
+/// disagree: the hash could see a stale redraw request while the actual render moved on, or a
+/// change could be drawn without ever bumping the damage hash that gates sending it to the
+/// device.
+struct ScriptedValues {
+    background: Option<Result<ScriptValue, ScriptError>>,
+    text: Vec<Option<Result<ScriptValue, ScriptError>>>,
+}
+
+impl ScriptedValues {
+    /// Placeholder for a component that [component_has_script] says has no scripts to
+    /// evaluate, so [draw_background]/[draw_foreground] can take the same `&ScriptedValues`
+    /// parameter on every call site without a sibling non-scripted code path
+    fn none(component: &RendererComponent) -> ScriptedValues {
+        ScriptedValues {
+            background: None,
+            text: vec![None; component.text.len()],
+        }
+    }
+}
+
+/// Evaluates every script `component` carries (its background, plus each text entry's own
+/// script) exactly once against a single `(time)` sample, see [ScriptedValues]
+fn evaluate_scripts(component: &RendererComponent, key: u8) -> ScriptedValues {
+    let ctx = ScriptContext { key, time: current_script_time() };
+
+    let background = match &component.background {
+        ButtonBackground::Script(source) => Some(eval_script(source, &ctx)),
+        _ => None,
+    };
+
+    let text = component.text.iter()
+        .map(|text| text.script.as_ref().map(|source| eval_script(source, &ctx)))
+        .collect();
+
+    ScriptedValues { background, text }
+}
+
+/// Folds an evaluated script result into `hasher` the same way regardless of which script
+/// (background or a particular text entry) it came from
+fn hash_script_result(result: &Option<Result<ScriptValue, ScriptError>>, hasher: &mut dyn Hasher) {
+    result.as_ref()
+        .and_then(|r| r.as_ref().ok())
+        .cloned()
+        .map(ScriptValue::into_text)
+        .hash(hasher);
+}
+
+/// Whether `component` needs to be re-evaluated every frame instead of relying on the static-content cache
+fn component_has_script(component: &RendererComponent) -> bool {
+    matches!(component.background, ButtonBackground::Script(_)) || component.text.iter().any(|text| text.script.is_some())
+}
+
+/// Compares `hash` against the last hash written to `key` and records it, so callers only
+/// push an image to the device when it actually changed. Shared between [redraw] and
+/// [process_animations], since both write to the same streamdeck and should agree on what
+/// was last sent.
+fn mark_if_changed(damage: &mut HashMap<u8, u64>, key: u8, hash: u64) -> bool {
+    if damage.get(&key) == Some(&hash) {
+        false
+    } else {
+        damage.insert(key, hash);
+        true
+    }
+}
+
+/// Folds a component's already-evaluated `scripted` text/background outputs into its hash,
+/// since their content isn't captured by deriving `Hash` on the static struct fields alone.
+/// Takes the evaluated [ScriptedValues] rather than re-running the scripts itself, so the hash
+/// this gates a redraw on always matches what [draw_background]/[draw_foreground] go on to draw.
+fn hash_scripted_component(core: &CoreHandle, button: &UniqueButton, component: &RendererComponent, scripted: &ScriptedValues) -> u64 {
+    let mut hasher: Box<dyn Hasher> = Box::new(DefaultHasher::new());
+    component.hash(&mut hasher);
+
+    hash_script_result(&scripted.background, &mut *hasher);
+
+    for text in &scripted.text {
+        hash_script_result(text, &mut *hasher);
+    }
+
+    for (_, module) in core.core.module_manager.read_rendering_modules_map().deref() {
+        module.render_hash(core.clone_for(module), button, &mut hasher);
+    }
+
+    hasher.finish()
+}
+
+fn draw_background(renderer: &RendererComponent, core: &CoreHandle, missing: &DynamicImage, counters: &mut HashMap<String, AnimationCounter>, key: u8, scripted: &ScriptedValues) -> DynamicImage {
     match &renderer.background {
         ButtonBackground::Solid(color) => {
             image_from_solid(core.core.image_size, Rgba([color.0, color.1, color.2, 255]))
@@ -424,25 +668,62 @@ fn draw_background(renderer: &RendererComponent, core: &CoreHandle, missing: &Dy
                 missing.clone()
             }
         }
+
+        ButtonBackground::Script(_) => {
+            match &scripted.background {
+                Some(Ok(value)) => {
+                    let (r, g, b, a) = value.clone().into_color();
+                    image_from_solid(core.core.image_size, Rgba([r, g, b, a]))
+                }
+                Some(Err(err)) => {
+                    log::warn!("Button background script on key {} failed: {}", key, err);
+                    missing.clone()
+                }
+                // The background isn't a script, or [evaluate_scripts] wasn't called with this
+                // component (shouldn't happen: every caller of draw_background evaluates scripts
+                // first); either way there's nothing evaluated to draw.
+                None => missing.clone(),
+            }
+        }
+
+        // Screencast frames are drawn by process_animations as they arrive from the capture
+        // thread, without going through this function. redraw does call it for a screencast
+        // button, which then shows `missing` until the next frame arrives
+        ButtonBackground::Screencast(_) => missing.clone(),
     }
 }
 
-fn draw_foreground(renderer: &RendererComponent, button: &UniqueButton, mut background: DynamicImage, core: &CoreHandle) -> DynamicImage {
+fn draw_foreground(renderer: &RendererComponent, button: &UniqueButton, mut background: DynamicImage, core: &CoreHandle, key: u8, scripted: &ScriptedValues) -> DynamicImage {
     // Render any additional things plugins want displayed
     for (_, module) in core.core.module_manager.read_rendering_modules_map().deref() {
         module.render(core.clone_for(module), button, &mut background);
     }
 
 
-    for button_text in &renderer.text {
-        let text = button_text.text.as_str();
+    for (index, button_text) in renderer.text.iter().enumerate() {
+        let scripted_text;
+        let text = if button_text.script.is_some() {
+            scripted_text = match scripted.text.get(index) {
+                Some(Some(Ok(value))) => value.clone().into_text(),
+                Some(Some(Err(err))) => {
+                    log::warn!("Button text script on key {} failed: {}", key, err);
+                    button_text.text.clone()
+                }
+                // Same "shouldn't happen" case as [draw_background]'s `None` arm
+                _ => button_text.text.clone(),
+            };
+
+            scripted_text.as_str()
+        } else {
+            button_text.text.as_str()
+        };
         let scale = Scale { x: button_text.scale.0, y: button_text.scale.1 };
         let align = button_text.alignment.clone();
         let padding = button_text.padding;
         let offset = button_text.offset.clone();
         let color = button_text.color.clone();
 
-        if let Some(font) = get_font_from_collection(&button_text.font) {
+        if let Some(font) = resolve_font(&button_text.font) {
             if let Some(shadow) = &button_text.shadow {
                 render_aligned_shadowed_text_on_image(
                     core.core.image_size,
@@ -476,8 +757,8 @@ fn draw_foreground(renderer: &RendererComponent, button: &UniqueButton, mut back
     background
 }
 
-// TODO: Get rid of this, instead use process_animations, just add difference tracking and only update images with streamdeck when necessary
-fn redraw(core: &CoreHandle, streamdeck: &mut StreamDeck, state: &RendererState, missing: &DynamicImage, cache: &mut HashMap<u64, DynamicImage>, renderer_map: &mut HashMap<u8, (UniqueButton, RendererComponent)>, counters: &mut HashMap<String, AnimationCounter>) {
+// TODO: Get rid of this, instead use process_animations
+fn redraw(core: &CoreHandle, streamdeck: &mut StreamDeck, state: &RendererState, missing: &DynamicImage, cache: &mut HashMap<u64, DynamicImage>, renderer_map: &mut HashMap<u8, (UniqueButton, RendererComponent)>, counters: &mut HashMap<String, AnimationCounter>, damage: &mut HashMap<u8, u64>) {
     let current_screen = get_current_screen(&core);
 
     if current_screen.is_none() {
@@ -504,6 +785,27 @@ fn redraw(core: &CoreHandle, streamdeck: &mut StreamDeck, state: &RendererState,
                     }
                 }
 
+                // Scripted content has no stable hash to cache against, so it's evaluated and drawn
+                // here without the cache; process_animations re-evaluates it on every later frame,
+                // see crate::core::scripting
+                if component_has_script(&component) {
+                    let scripted = evaluate_scripts(&component, i);
+                    let hash = hash_scripted_component(core, button, &component, &scripted);
+
+                    if mark_if_changed(damage, i, hash) {
+                        let background = draw_background(&component, &core, missing, counters, i, &scripted);
+                        let image = draw_foreground(&component, button, background, &core, i, &scripted);
+
+                        current_images.insert(i, image.clone());
+                        streamdeck.write_button_image(i, &convert_image(&streamdeck.kind(), image.clone())).ok();
+                        publish_preview(state, i, &image);
+                    } else if let Some(image) = state.current_images.read().unwrap().get(&i) {
+                        // Unchanged, but still part of the snapshot new subscribers receive
+                        current_images.insert(i, image.clone());
+                    }
+                    continue;
+                }
+
                 // Caching if image is just like any other
                 let mut hasher: Box<dyn Hasher> = Box::new(DefaultHasher::new());
 
@@ -514,25 +816,37 @@ fn redraw(core: &CoreHandle, streamdeck: &mut StreamDeck, state: &RendererState,
 
                 let hash = hasher.finish();
 
-                if let Some(image) = cache.get(&hash) {
+                if !mark_if_changed(damage, i, hash) {
+                    // Already showing this exact image, nothing to push to the device, but it
+                    // stays in the snapshot new subscribers receive
+                    if let Some(image) = cache.get(&hash) {
+                        current_images.insert(i, image.clone());
+                    }
+                } else if let Some(image) = cache.get(&hash) {
                     current_images.insert(i, image.clone());
                     streamdeck.write_button_image(i, &convert_image(&streamdeck.kind(), image.clone())).ok();
+                    publish_preview(state, i, image);
                 } else {
-                    let image = draw_foreground(&component, button, draw_background(&component, &core, missing, counters), &core);
+                    let scripted = ScriptedValues::none(&component);
+                    let background = draw_background(&component, &core, missing, counters, i, &scripted);
+                    let image = draw_foreground(&component, button, background, &core, i, &scripted);
 
                     cache.insert(hash, image.clone());
 
                     current_images.insert(i, image.clone());
-                    streamdeck.write_button_image(i, &convert_image(&streamdeck.kind(), image)).ok();
+                    streamdeck.write_button_image(i, &convert_image(&streamdeck.kind(), image.clone())).ok();
+                    publish_preview(state, i, &image);
                 }
             } else {
                 renderer_map.remove(&i);
+                damage.remove(&i);
 
                 current_images.insert(i, image_from_solid(core.core.image_size, Rgba([0, 0, 0, 255])));
                 streamdeck.set_button_rgb(i, &Colour { r: 0, g: 0, b: 0 }).ok();
             }
         } else {
             renderer_map.remove(&i);
+            damage.remove(&i);
 
             streamdeck.set_button_rgb(i, &Colour { r: 0, g: 0, b: 0 }).ok();
         }
@@ -554,6 +868,16 @@ pub enum ButtonBackground {
     VerticalGradient(Color, Color),
     ExistingImage(String),
     NewImage(String),
+
+    /// Color produced by evaluating a script every frame, see [crate::core::scripting]
+    Script(String),
+
+    /// Live capture of a monitor or window, streamed in via PipeWire, keyed by a session
+    /// identifier. The identifier only distinguishes separate concurrently-running capture
+    /// sessions (so two buttons aren't forced to share one); which monitor or window is
+    /// actually captured is picked interactively through the portal's own dialog each time a
+    /// session starts, not by this string. See [crate::core::screencast]
+    Screencast(String),
 }
 
 impl Default for ButtonBackground {
@@ -573,6 +897,11 @@ pub struct ButtonText {
     pub offset: (f32, f32),
     pub color: Color,
     pub shadow: Option<ButtonTextShadow>,
+
+    /// When set, overrides `text` every frame with the result of evaluating this script,
+    /// see [crate::core::scripting]
+    #[serde(default)]
+    pub script: Option<String>,
 }
 
 impl Hash for ButtonText {
@@ -587,6 +916,7 @@ impl Hash for ButtonText {
         ((self.offset.1 * 100.0) as i32).hash(state);
         self.color.hash(state);
         self.shadow.hash(state);
+        self.script.hash(state);
     }
 }
 
@@ -1,14 +1,28 @@
 //! Requests related to buttons
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use streamduck_core::core::button::Button;
 use streamduck_core::core::CoreHandle;
+use streamduck_core::core::methods::{button_down, button_up};
 use streamduck_core::modules::components::UIPathValue;
-use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
+use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_error_packet, send_packet, JsonRpcError, SocketData, SocketHandle, SocketPacket, INVALID_PARAMS};
 use streamduck_core::util::{button_to_raw, make_button_unique};
 use crate::daemon_data::{DaemonListener, DaemonRequest};
 use std::ops::Deref;
 use streamduck_core::async_trait;
 
+/// Reports `packet`'s params as malformed via a JSON-RPC [INVALID_PARAMS] error, used by every
+/// `process` impl in this module when [parse_packet_to_data] fails, so a caller that sends a
+/// request its params don't deserialize into gets a structured [JsonRpcError] back instead of
+/// the connection just going quiet on it
+async fn send_invalid_params(handle: SocketHandle<'_>, packet: &SocketPacket, message: &str) {
+    send_error_packet(handle, packet, JsonRpcError {
+        code: INVALID_PARAMS,
+        message: message.to_string(),
+        data: None,
+    }).await.ok();
+}
+
 /// Request for getting a button from current screen on a device
 #[derive(Serialize, Deserialize)]
 pub struct GetButton {
@@ -52,6 +66,8 @@ impl DaemonRequest for GetButton {
             } else {
                 send_packet(handle, packet, &GetButtonResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -100,6 +116,8 @@ impl DaemonRequest for SetButton {
             } else {
                 send_packet(handle, packet, &SetButtonResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -147,6 +165,8 @@ impl DaemonRequest for ClearButton {
             } else {
                 send_packet(handle, packet, &ClearButtonResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -194,6 +214,8 @@ impl DaemonRequest for NewButton {
             } else {
                 send_packet(handle, packet, &NewButtonResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -260,6 +282,8 @@ impl DaemonRequest for NewButtonFromComponent {
             } else {
                 send_packet(handle, packet, &NewButtonFromComponentResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -309,6 +333,8 @@ impl DaemonRequest for AddComponent {
             } else {
                 send_packet(handle, packet, &AddComponentResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -359,6 +385,8 @@ impl DaemonRequest for GetComponentValues {
             } else {
                 send_packet(handle, packet, &GetComponentValuesResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -409,6 +437,8 @@ impl DaemonRequest for AddComponentValue {
             } else {
                 send_packet(handle, packet, &AddComponentValueResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -460,6 +490,8 @@ impl DaemonRequest for RemoveComponentValue {
             } else {
                 send_packet(handle, packet, &RemoveComponentValueResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -510,6 +542,8 @@ impl DaemonRequest for SetComponentValue {
             } else {
                 send_packet(handle, packet, &SetComponentValueResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -558,10 +592,21 @@ impl DaemonRequest for RemoveComponent {
             } else {
                 send_packet(handle, packet, &RemoveComponentResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
 
+/// A group of buttons held in the clipboard, keyed by the grid position they were copied
+/// from. `anchor` is the lowest of those keys, so [PasteButtons] can re-anchor the whole
+/// group relative to a different key without needing to know the device's grid dimensions.
+#[derive(Clone)]
+pub struct ClipboardSpace {
+    pub anchor: u8,
+    pub buttons: Vec<(u8, Button)>,
+}
+
 /// Request for checking clipboard status
 #[derive(Serialize, Deserialize)]
 pub enum ClipboardStatusResult {
@@ -625,7 +670,10 @@ impl DaemonRequest for CopyButton {
 
                 if let Some(button) = wrapped_core.get_button(request.key).await {
                     let mut lock = listener.clipboard.lock().await;
-                    *lock = Some(button.read().await.deref().clone());
+                    *lock = Some(ClipboardSpace {
+                        anchor: request.key,
+                        buttons: vec![(request.key, button.read().await.deref().clone())],
+                    });
                     send_packet(handle, packet, &CopyButtonResult::Copied).await.ok();
                 } else {
                     send_packet(handle, packet, &CopyButtonResult::NoButton).await.ok();
@@ -633,6 +681,8 @@ impl DaemonRequest for CopyButton {
             } else {
                 send_packet(handle, packet, &CopyButtonResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
@@ -674,10 +724,12 @@ impl DaemonRequest for PasteButton {
 
                 let clipboard = listener.clipboard.lock().await;
 
-                if clipboard.is_some() {
-                    if wrapped_core.paste_button(request.key, clipboard.as_ref().unwrap()).await {
-                        send_packet(handle, packet, &PasteButtonResult::Pasted).await.ok();
-                        return;
+                if let Some(space) = clipboard.as_ref() {
+                    if let Some((_, button)) = space.buttons.iter().find(|(key, _)| *key == space.anchor) {
+                        if wrapped_core.paste_button(request.key, button).await {
+                            send_packet(handle, packet, &PasteButtonResult::Pasted).await.ok();
+                            return;
+                        }
                     }
                 }
 
@@ -685,6 +737,586 @@ impl DaemonRequest for PasteButton {
             } else {
                 send_packet(handle, packet, &PasteButtonResult::DeviceNotFound).await.ok();
             }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
+        }
+    }
+}
+
+/// Request to copy several buttons at once, keyed by their grid positions, so the whole
+/// group can be re-anchored relative to a different key on paste, see [PasteButtons]
+#[derive(Serialize, Deserialize)]
+pub struct CopyButtons {
+    pub serial_number: String,
+    pub keys: Vec<u8>,
+}
+
+/// Response of [CopyButtons] request
+#[derive(Serialize, Deserialize)]
+pub enum CopyButtonsResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if none of the requested keys had a button on them
+    NoButtons,
+
+    /// Sent if successfully copied the buttons
+    Copied,
+}
+
+impl SocketData for CopyButtons {
+    const NAME: &'static str = "copy_buttons";
+}
+
+impl SocketData for CopyButtonsResult {
+    const NAME: &'static str = "copy_buttons";
+}
+
+#[async_trait]
+impl DaemonRequest for CopyButtons {
+    async fn process(listener: &DaemonListener, handle: SocketHandle<'_>, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<CopyButtons>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number).await {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                let mut buttons = vec![];
+
+                for &key in &request.keys {
+                    if let Some(button) = wrapped_core.get_button(key).await {
+                        buttons.push((key, button.read().await.deref().clone()));
+                    }
+                }
+
+                if let Some(anchor) = buttons.iter().map(|(key, _)| *key).min() {
+                    let mut lock = listener.clipboard.lock().await;
+                    *lock = Some(ClipboardSpace { anchor, buttons });
+
+                    send_packet(handle, packet, &CopyButtonsResult::Copied).await.ok();
+                } else {
+                    send_packet(handle, packet, &CopyButtonsResult::NoButtons).await.ok();
+                }
+            } else {
+                send_packet(handle, packet, &CopyButtonsResult::DeviceNotFound).await.ok();
+            }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
+        }
+    }
+}
+
+/// Request to copy every occupied key on the current screen, see [PasteButtons]
+#[derive(Serialize, Deserialize)]
+pub struct CopyScreen {
+    pub serial_number: String,
+}
+
+/// Response of [CopyScreen] request
+#[derive(Serialize, Deserialize)]
+pub enum CopyScreenResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if the current screen has no buttons on it
+    NoButtons,
+
+    /// Sent if successfully copied the screen
+    Copied,
+}
+
+impl SocketData for CopyScreen {
+    const NAME: &'static str = "copy_screen";
+}
+
+impl SocketData for CopyScreenResult {
+    const NAME: &'static str = "copy_screen";
+}
+
+#[async_trait]
+impl DaemonRequest for CopyScreen {
+    async fn process(listener: &DaemonListener, handle: SocketHandle<'_>, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<CopyScreen>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number).await {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                let mut buttons = vec![];
+
+                for key in 0..wrapped_core.core.key_count {
+                    if let Some(button) = wrapped_core.get_button(key).await {
+                        buttons.push((key, button.read().await.deref().clone()));
+                    }
+                }
+
+                if let Some(anchor) = buttons.iter().map(|(key, _)| *key).min() {
+                    let mut lock = listener.clipboard.lock().await;
+                    *lock = Some(ClipboardSpace { anchor, buttons });
+
+                    send_packet(handle, packet, &CopyScreenResult::Copied).await.ok();
+                } else {
+                    send_packet(handle, packet, &CopyScreenResult::NoButtons).await.ok();
+                }
+            } else {
+                send_packet(handle, packet, &CopyScreenResult::DeviceNotFound).await.ok();
+            }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
+        }
+    }
+}
+
+/// Request to paste the whole clipboard group back onto a device, re-anchored so the key
+/// that was the group's anchor on copy lands on `target_key`, and every other button shifts
+/// by the same offset. Keys that fall off the device after the shift are skipped.
+#[derive(Serialize, Deserialize)]
+pub struct PasteButtons {
+    pub serial_number: String,
+    pub target_key: u8,
+}
+
+/// Response of [PasteButtons] request
+#[derive(Serialize, Deserialize)]
+pub enum PasteButtonsResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if clipboard is empty
+    ClipboardEmpty,
+
+    /// Sent if every button in range failed to paste, see [PasteButtonResult::FailedToPaste]
+    FailedToPaste,
+
+    /// Sent if at least one button in range pasted but at least one other failed
+    PartiallyPasted,
+
+    /// Sent if every button in range was successfully pasted
+    Pasted,
+}
+
+impl SocketData for PasteButtons {
+    const NAME: &'static str = "paste_buttons";
+}
+
+impl SocketData for PasteButtonsResult {
+    const NAME: &'static str = "paste_buttons";
+}
+
+#[async_trait]
+impl DaemonRequest for PasteButtons {
+    async fn process(listener: &DaemonListener, handle: SocketHandle<'_>, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<PasteButtons>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number).await {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                let clipboard = listener.clipboard.lock().await;
+
+                if let Some(space) = clipboard.as_ref() {
+                    let offset = request.target_key as i16 - space.anchor as i16;
+
+                    let mut attempted = 0;
+                    let mut pasted = 0;
+
+                    for (key, button) in &space.buttons {
+                        let new_key = *key as i16 + offset;
+
+                        if new_key < 0 || new_key >= wrapped_core.core.key_count as i16 {
+                            continue;
+                        }
+
+                        attempted += 1;
+
+                        if wrapped_core.paste_button(new_key as u8, button).await {
+                            pasted += 1;
+                        }
+                    }
+
+                    listener.config.sync_images(&request.serial_number).await;
+
+                    let result = if pasted == 0 {
+                        PasteButtonsResult::FailedToPaste
+                    } else if pasted < attempted {
+                        PasteButtonsResult::PartiallyPasted
+                    } else {
+                        PasteButtonsResult::Pasted
+                    };
+
+                    send_packet(handle, packet, &result).await.ok();
+                } else {
+                    send_packet(handle, packet, &PasteButtonsResult::ClipboardEmpty).await.ok();
+                }
+            } else {
+                send_packet(handle, packet, &PasteButtonsResult::DeviceNotFound).await.ok();
+            }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
+        }
+    }
+}
+
+/// Backing store for [AddVirtualDevice]/[SetVirtualDeviceImage]/[GetVirtualDeviceImage].
+///
+/// This registry is separate from `core_manager`: a virtual device has no `SDCore`, since
+/// `SDCore::new` takes a real `streamdeck::StreamDeck` connection. The three requests above
+/// record and read back bytes under a serial number of their own, and nothing else. Since
+/// `core_manager.get_device` never resolves a virtual serial number,
+/// `SetButton`/`ClearButton`/`NewButtonFromComponent`/`AddComponentValue`/etc. cannot be driven
+/// against a virtual device; those still require real hardware.
+static VIRTUAL_DEVICES: std::sync::Mutex<Option<HashMap<String, VirtualDevice>>> = std::sync::Mutex::new(None);
+
+struct VirtualDevice {
+    key_count: u8,
+    images: HashMap<u8, Vec<u8>>,
+}
+
+fn with_virtual_devices<T>(f: impl FnOnce(&mut HashMap<String, VirtualDevice>) -> T) -> T {
+    f(VIRTUAL_DEVICES.lock().unwrap().get_or_insert_with(HashMap::new))
+}
+
+/// Request to register a software-only device under `serial_number`, so [SetVirtualDeviceImage]
+/// and [GetVirtualDeviceImage] can be exercised in tests without real Stream Deck hardware
+/// attached. The other requests in this module don't see it, see [VIRTUAL_DEVICES].
+#[derive(Serialize, Deserialize)]
+pub struct AddVirtualDevice {
+    pub serial_number: String,
+    pub key_count: u8,
+}
+
+/// Response of [AddVirtualDevice] request
+#[derive(Serialize, Deserialize)]
+pub enum AddVirtualDeviceResult {
+    /// Sent if a device with this serial number is already registered, either as a real
+    /// `core_manager` device or a previously-added virtual one
+    AlreadyExists,
+
+    /// Sent once the virtual device is registered and ready to record images
+    Created,
+}
+
+impl SocketData for AddVirtualDevice {
+    const NAME: &'static str = "add_virtual_device";
+}
+
+impl SocketData for AddVirtualDeviceResult {
+    const NAME: &'static str = "add_virtual_device";
+}
+
+#[async_trait]
+impl DaemonRequest for AddVirtualDevice {
+    async fn process(listener: &DaemonListener, handle: SocketHandle<'_>, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<AddVirtualDevice>(packet) {
+            let already_exists = listener.core_manager.get_device(&request.serial_number).await.is_some()
+                || with_virtual_devices(|devices| devices.contains_key(&request.serial_number));
+
+            if already_exists {
+                send_packet(handle, packet, &AddVirtualDeviceResult::AlreadyExists).await.ok();
+            } else {
+                with_virtual_devices(|devices| devices.insert(request.serial_number.clone(), VirtualDevice {
+                    key_count: request.key_count,
+                    images: HashMap::new(),
+                }));
+
+                send_packet(handle, packet, &AddVirtualDeviceResult::Created).await.ok();
+            }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
+        }
+    }
+}
+
+/// Request to record the bytes rendered for a key on a virtual device, standing in for the
+/// write a real render pass would otherwise push over USB. See [VIRTUAL_DEVICES].
+#[derive(Serialize, Deserialize)]
+pub struct SetVirtualDeviceImage {
+    pub serial_number: String,
+    pub key: u8,
+    pub image: Vec<u8>,
+}
+
+/// Response of [SetVirtualDeviceImage] request
+#[derive(Serialize, Deserialize)]
+pub enum SetVirtualDeviceImageResult {
+    /// Sent if no virtual device with this serial number was registered via [AddVirtualDevice]
+    DeviceNotFound,
+
+    /// Sent if `key` is outside the device's registered key count
+    InvalidKey,
+
+    /// Sent once `image` is recorded
+    Recorded,
+}
+
+impl SocketData for SetVirtualDeviceImage {
+    const NAME: &'static str = "set_virtual_device_image";
+}
+
+impl SocketData for SetVirtualDeviceImageResult {
+    const NAME: &'static str = "set_virtual_device_image";
+}
+
+#[async_trait]
+impl DaemonRequest for SetVirtualDeviceImage {
+    async fn process(_listener: &DaemonListener, handle: SocketHandle<'_>, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SetVirtualDeviceImage>(packet) {
+            let result = with_virtual_devices(|devices| {
+                let Some(device) = devices.get_mut(&request.serial_number) else {
+                    return SetVirtualDeviceImageResult::DeviceNotFound;
+                };
+
+                if request.key >= device.key_count {
+                    return SetVirtualDeviceImageResult::InvalidKey;
+                }
+
+                device.images.insert(request.key, request.image.clone());
+                SetVirtualDeviceImageResult::Recorded
+            });
+
+            send_packet(handle, packet, &result).await.ok();
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
+        }
+    }
+}
+
+/// Request to read back the bytes currently recorded for a virtual device's key, meant for
+/// asserting against in a test. See [VIRTUAL_DEVICES].
+#[derive(Serialize, Deserialize)]
+pub struct GetVirtualDeviceImage {
+    pub serial_number: String,
+    pub key: u8,
+}
+
+/// Response of [GetVirtualDeviceImage] request
+#[derive(Serialize, Deserialize)]
+pub enum GetVirtualDeviceImageResult {
+    /// Sent if no virtual device with this serial number was registered via [AddVirtualDevice]
+    DeviceNotFound,
+
+    /// Sent if `key` is outside the device's registered key count
+    InvalidKey,
+
+    /// Sent if `key` hasn't had an image recorded via [SetVirtualDeviceImage] yet
+    NoImage,
+
+    /// Sent with the bytes last recorded for `key`
+    Image(Vec<u8>),
+}
+
+impl SocketData for GetVirtualDeviceImage {
+    const NAME: &'static str = "get_virtual_device_image";
+}
+
+impl SocketData for GetVirtualDeviceImageResult {
+    const NAME: &'static str = "get_virtual_device_image";
+}
+
+#[async_trait]
+impl DaemonRequest for GetVirtualDeviceImage {
+    async fn process(_listener: &DaemonListener, handle: SocketHandle<'_>, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<GetVirtualDeviceImage>(packet) {
+            let result = with_virtual_devices(|devices| {
+                let Some(device) = devices.get(&request.serial_number) else {
+                    return GetVirtualDeviceImageResult::DeviceNotFound;
+                };
+
+                if request.key >= device.key_count {
+                    return GetVirtualDeviceImageResult::InvalidKey;
+                }
+
+                match device.images.get(&request.key) {
+                    Some(image) => GetVirtualDeviceImageResult::Image(image.clone()),
+                    None => GetVirtualDeviceImageResult::NoImage,
+                }
+            });
+
+            send_packet(handle, packet, &result).await.ok();
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
+        }
+    }
+}
+
+/// Request to simulate a physical press of a button, dispatching it down the same action
+/// path a real key-down event from the device would take
+#[derive(Serialize, Deserialize)]
+pub struct SimulateButtonPress {
+    pub serial_number: String,
+    pub key: u8,
+}
+
+/// Response of [SimulateButtonPress] request
+#[derive(Serialize, Deserialize)]
+pub enum SimulateButtonPressResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if there's no button on that key to trigger
+    NoButton,
+
+    /// Sent if the press was dispatched
+    Triggered,
+}
+
+impl SocketData for SimulateButtonPress {
+    const NAME: &'static str = "simulate_button_press";
+}
+
+impl SocketData for SimulateButtonPressResult {
+    const NAME: &'static str = "simulate_button_press";
+}
+
+#[async_trait]
+impl DaemonRequest for SimulateButtonPress {
+    async fn process(listener: &DaemonListener, handle: SocketHandle<'_>, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SimulateButtonPress>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number).await {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                if wrapped_core.get_button(request.key).await.is_some() {
+                    button_down(&wrapped_core, request.key);
+                    send_packet(handle, packet, &SimulateButtonPressResult::Triggered).await.ok();
+                } else {
+                    send_packet(handle, packet, &SimulateButtonPressResult::NoButton).await.ok();
+                }
+            } else {
+                send_packet(handle, packet, &SimulateButtonPressResult::DeviceNotFound).await.ok();
+            }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
+        }
+    }
+}
+
+/// Request to simulate a physical release of a button, following up a [SimulateButtonPress]
+#[derive(Serialize, Deserialize)]
+pub struct SimulateButtonRelease {
+    pub serial_number: String,
+    pub key: u8,
+}
+
+/// Response of [SimulateButtonRelease] request
+#[derive(Serialize, Deserialize)]
+pub enum SimulateButtonReleaseResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if there's no button on that key to trigger
+    NoButton,
+
+    /// Sent if the release was dispatched
+    Triggered,
+}
+
+impl SocketData for SimulateButtonRelease {
+    const NAME: &'static str = "simulate_button_release";
+}
+
+impl SocketData for SimulateButtonReleaseResult {
+    const NAME: &'static str = "simulate_button_release";
+}
+
+#[async_trait]
+impl DaemonRequest for SimulateButtonRelease {
+    async fn process(listener: &DaemonListener, handle: SocketHandle<'_>, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SimulateButtonRelease>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number).await {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                if wrapped_core.get_button(request.key).await.is_some() {
+                    button_up(&wrapped_core, request.key);
+                    send_packet(handle, packet, &SimulateButtonReleaseResult::Triggered).await.ok();
+                } else {
+                    send_packet(handle, packet, &SimulateButtonReleaseResult::NoButton).await.ok();
+                }
+            } else {
+                send_packet(handle, packet, &SimulateButtonReleaseResult::DeviceNotFound).await.ok();
+            }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
+        }
+    }
+}
+
+/// A single operation within a [BatchComponentEdit], mirroring the payloads of
+/// [AddComponentValue], [RemoveComponentValue] and [SetComponentValue] respectively
+#[derive(Serialize, Deserialize)]
+pub enum ComponentOp {
+    Add { path: String },
+    Remove { path: String, index: usize },
+    Set { value: UIPathValue },
+}
+
+/// Request to apply several component value edits to a single button as one unit, so a form
+/// with many fields only triggers one render instead of one per field
+#[derive(Serialize, Deserialize)]
+pub struct BatchComponentEdit {
+    pub serial_number: String,
+    pub key: u8,
+    pub component_name: String,
+    pub ops: Vec<ComponentOp>,
+}
+
+/// Response of [BatchComponentEdit] request
+#[derive(Serialize, Deserialize)]
+pub enum BatchComponentEditResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if there's no button on that key to edit
+    NoButton,
+
+    /// Sent if an op failed; the button is restored to the state it was in before the batch
+    /// started and no ops are applied, carrying the index of the op that failed
+    FailedAt(usize),
+
+    /// Sent if every op applied and the button was re-rendered once
+    Applied,
+}
+
+impl SocketData for BatchComponentEdit {
+    const NAME: &'static str = "batch_component_edit";
+}
+
+impl SocketData for BatchComponentEditResult {
+    const NAME: &'static str = "batch_component_edit";
+}
+
+#[async_trait]
+impl DaemonRequest for BatchComponentEdit {
+    async fn process(listener: &DaemonListener, handle: SocketHandle<'_>, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<BatchComponentEdit>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number).await {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                if let Some(button) = wrapped_core.get_button(request.key).await {
+                    let original = button.read().await.deref().clone();
+                    let mut failed_at = None;
+
+                    for (index, op) in request.ops.into_iter().enumerate() {
+                        let applied = match op {
+                            ComponentOp::Add { path } => wrapped_core.add_element_component_value(request.key, &request.component_name, &path).await,
+                            ComponentOp::Remove { path, index: element_index } => wrapped_core.remove_element_component_value(request.key, &request.component_name, &path, element_index).await,
+                            ComponentOp::Set { value } => wrapped_core.set_component_value_by_path(request.key, &request.component_name, value).await,
+                        };
+
+                        if !applied {
+                            failed_at = Some(index);
+                            break;
+                        }
+                    }
+
+                    if let Some(index) = failed_at {
+                        wrapped_core.set_button(request.key, make_button_unique(original)).await;
+                        send_packet(handle, packet, &BatchComponentEditResult::FailedAt(index)).await.ok();
+                    } else {
+                        listener.config.sync_images(&request.serial_number).await;
+                        send_packet(handle, packet, &BatchComponentEditResult::Applied).await.ok();
+                    }
+                } else {
+                    send_packet(handle, packet, &BatchComponentEditResult::NoButton).await.ok();
+                }
+            } else {
+                send_packet(handle, packet, &BatchComponentEditResult::DeviceNotFound).await.ok();
+            }
+        } else {
+            send_invalid_params(handle, packet, "invalid params").await;
         }
     }
 }
\ No newline at end of file